@@ -0,0 +1,357 @@
+// An optional JIT backend, sitting alongside the tree-walking `execute` in
+// the parent module. It lowers the same `Vec<Instructions>` produced by
+// `compile` into native code with Cranelift instead of interpreting it, and
+// shares the same tape model: a pointer into a `Config::tape_len`-sized
+// buffer (though `TapeBehavior::Growing` isn't supported here — see
+// `run_jit`).
+use crate::{BfError, Config, EofBehavior, Instructions, TapeBehavior};
+use std::io::BufRead;
+
+use cranelift::prelude::*;
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{FuncId, Linkage, Module};
+
+// The pieces of interpreter state the JIT'd code needs to call back into:
+// the tape, the input reader, the EOF convention, and the collected output.
+// A single pointer to this struct is threaded through as the JIT function's
+// only argument.
+//
+// `memory` is heap-allocated so `Config::tape_len` can vary; `memory_ptr`
+// caches its base address so the JIT'd code can address cells without going
+// through Rust's `Vec` representation. It stays valid because this struct
+// never resizes `memory` after construction (the JIT backend doesn't
+// support `TapeBehavior::Growing` — see `run_jit`).
+struct JitContext<'a> {
+    memory: Vec<u8>,
+    memory_ptr: *mut u8,
+    input: &'a mut dyn BufRead,
+    eof_behavior: &'a EofBehavior,
+    output: Vec<u8>,
+    print_live: bool,
+}
+
+extern "C" fn jit_put_char(context: *mut JitContext, value: u8) {
+    let context = unsafe { &mut *context };
+    context.output.push(value);
+    if context.print_live {
+        print!("{}", value as char);
+    }
+}
+
+// `current` is the cell the JIT'd code is about to overwrite — it lives in a
+// Cranelift `Variable`, not in `JitContext`, so the caller passes it in
+// rather than this trampoline guessing which cell is "current".
+extern "C" fn jit_get_char(context: *mut JitContext, current: u8) -> u8 {
+    let context = unsafe { &mut *context };
+    match context.input.fill_buf() {
+        Ok(buf) if !buf.is_empty() => {
+            let byte = buf[0];
+            context.input.consume(1);
+            byte
+        }
+        _ => match context.eof_behavior {
+            EofBehavior::Unchanged => current,
+            EofBehavior::Zero => 0,
+            EofBehavior::NegOne => 255,
+        },
+    }
+}
+
+pub(crate) fn run_jit(
+    program: &[Instructions],
+    print_live: bool,
+    input: &mut impl BufRead,
+    eof_behavior: &EofBehavior,
+    config: &Config,
+) -> Result<String, BfError> {
+    // Growing the tape mid-run would require the JIT'd code to call back
+    // into Rust on every pointer move just to check for a reallocation;
+    // that defeats the point of JIT'ing, so it isn't supported here.
+    if matches!(config.tape_behavior, TapeBehavior::Growing) {
+        return Err(BfError::UnsupportedConfig);
+    }
+    // The generated code does raw unchecked pointer arithmetic on the tape
+    // buffer (see `compile_to_native`) — there are no bounds checks to
+    // honor `Config::checked` with, so reject it rather than silently
+    // skipping the check it promises.
+    if config.checked {
+        return Err(BfError::UnsupportedConfig);
+    }
+
+    let mut builder = JITBuilder::new(cranelift_module::default_libcall_names())
+        .expect("failed to set up the JIT builder");
+    builder.symbol("jit_put_char", jit_put_char as *const u8);
+    builder.symbol("jit_get_char", jit_get_char as *const u8);
+    let mut module = JITModule::new(builder);
+
+    let put_char_id = declare_trampoline(&mut module, "jit_put_char", &[types::I64, types::I8], None);
+    let get_char_id = declare_trampoline(
+        &mut module,
+        "jit_get_char",
+        &[types::I64, types::I8],
+        Some(types::I8),
+    );
+
+    let func_id = compile_to_native(&mut module, program, put_char_id, get_char_id, config);
+    module.finalize_definitions().expect("failed to finalize JIT code");
+
+    let compiled = module.get_finalized_function(func_id);
+    let compiled: extern "C" fn(*mut JitContext) = unsafe { std::mem::transmute(compiled) };
+
+    let mut memory = vec![0u8; config.tape_len];
+    let memory_ptr = memory.as_mut_ptr();
+    let mut context = JitContext {
+        memory,
+        memory_ptr,
+        input,
+        eof_behavior,
+        output: Vec::new(),
+        print_live,
+    };
+    compiled(&mut context as *mut JitContext);
+
+    // Safe to tear down now: `compiled` never escapes this call.
+    unsafe {
+        module.free_memory();
+    }
+    return Ok(String::from_utf8(context.output).unwrap());
+}
+
+fn declare_trampoline(
+    module: &mut JITModule,
+    name: &str,
+    params: &[types::Type],
+    returns: Option<types::Type>,
+) -> FuncId {
+    let mut signature = module.make_signature();
+    for param in params {
+        signature.params.push(AbiParam::new(*param));
+    }
+    if let Some(ret) = returns {
+        signature.returns.push(AbiParam::new(ret));
+    }
+    return module
+        .declare_function(name, Linkage::Import, &signature)
+        .expect("failed to declare JIT trampoline");
+}
+
+// Lowers the instruction stream into a single native function operating on
+// `*mut JitContext`: `Add`/`Sub`/`PointerLeft`/`PointerRight`/`Clear` become
+// a handful of native instructions, and `LoopStart`/`LoopEnd` become native
+// branches using the bracket targets `compile` already precomputed.
+fn compile_to_native(
+    module: &mut JITModule,
+    program: &[Instructions],
+    put_char_id: FuncId,
+    get_char_id: FuncId,
+    config: &Config,
+) -> FuncId {
+    let mut signature = module.make_signature();
+    signature.params.push(AbiParam::new(types::I64));
+    let func_id = module
+        .declare_function("bf_main", Linkage::Export, &signature)
+        .expect("failed to declare the compiled program");
+
+    let mut context = module.make_context();
+    context.func.signature = signature;
+    let mut builder_context = FunctionBuilderContext::new();
+    let mut builder = FunctionBuilder::new(&mut context.func, &mut builder_context);
+
+    let entry = builder.create_block();
+    builder.append_block_params_for_function_params(entry);
+    builder.switch_to_block(entry);
+    builder.seal_block(entry);
+    let context_ptr = builder.block_params(entry)[0];
+
+    // Load the tape's base address once; `memory_ptr` never changes after
+    // `JitContext` is constructed (see its doc comment).
+    let memory_ptr_offset = std::mem::offset_of!(JitContext, memory_ptr) as i32;
+    let memory_base = builder.ins().load(
+        types::I64,
+        MemFlags::trusted(),
+        context_ptr,
+        memory_ptr_offset,
+    );
+
+    let pointer_var = Variable::new(0);
+    builder.declare_var(pointer_var, types::I64);
+    let zero = builder.ins().iconst(types::I64, 0);
+    builder.def_var(pointer_var, zero);
+
+    let put_char_ref = module.declare_func_in_func(put_char_id, builder.func);
+    let get_char_ref = module.declare_func_in_func(get_char_id, builder.func);
+
+    // `Wrapping` tapes fold the pointer back onto [0, tape_len) after every
+    // move (see `wrap_pointer`); `Bounded` trusts the program (the JIT has no
+    // cheap way to surface a `Result` mid-run, so `run_jit` rejects
+    // `Config::checked` up front instead of emitting bounds checks here).
+    let wrap_len = match config.tape_behavior {
+        TapeBehavior::Wrapping => Some(config.tape_len as i64),
+        _ => None,
+    };
+
+    // One (body, after) block pair per matched loop, indexed by the
+    // `LoopStart` instruction's position, so `LoopEnd` can jump back in.
+    let mut loop_blocks = vec![None; program.len()];
+
+    // `offset_pointer` declares a fresh scratch `Variable` per `MoveMul`
+    // target; each one needs an id Cranelift hasn't seen before, since a
+    // whole program compiles into a single function/`FunctionBuilder` and
+    // `declare_var` only accepts one declaration per id.
+    let mut next_var_id = 1;
+
+    for (position, instruction) in program.iter().enumerate() {
+        match instruction {
+            Instructions::PointerLeft(num) => {
+                let pointer = builder.use_var(pointer_var);
+                let delta = builder.ins().iconst(types::I64, *num as i64);
+                let moved = builder.ins().iadd(pointer, delta);
+                let moved = wrap_pointer(&mut builder, moved, wrap_len);
+                builder.def_var(pointer_var, moved);
+            }
+            Instructions::PointerRight(num) => {
+                let pointer = builder.use_var(pointer_var);
+                let delta = builder.ins().iconst(types::I64, *num as i64);
+                let moved = builder.ins().isub(pointer, delta);
+                let moved = wrap_pointer(&mut builder, moved, wrap_len);
+                builder.def_var(pointer_var, moved);
+            }
+            Instructions::Add(num) => {
+                let cell = load_cell(&mut builder, memory_base, pointer_var);
+                let delta = builder.ins().iconst(types::I8, *num as i64);
+                let sum = builder.ins().iadd(cell, delta);
+                store_cell(&mut builder, memory_base, pointer_var, sum);
+            }
+            Instructions::Sub(num) => {
+                let cell = load_cell(&mut builder, memory_base, pointer_var);
+                let delta = builder.ins().iconst(types::I8, *num as i64);
+                let diff = builder.ins().isub(cell, delta);
+                store_cell(&mut builder, memory_base, pointer_var, diff);
+            }
+            Instructions::Clear => {
+                let zero = builder.ins().iconst(types::I8, 0);
+                store_cell(&mut builder, memory_base, pointer_var, zero);
+            }
+            Instructions::PutChar => {
+                let cell = load_cell(&mut builder, memory_base, pointer_var);
+                builder.ins().call(put_char_ref, &[context_ptr, cell]);
+            }
+            Instructions::GetChar => {
+                let current = load_cell(&mut builder, memory_base, pointer_var);
+                let call = builder.ins().call(get_char_ref, &[context_ptr, current]);
+                let value = builder.inst_results(call)[0];
+                store_cell(&mut builder, memory_base, pointer_var, value);
+            }
+            Instructions::MoveMul(targets) => {
+                let current = load_cell(&mut builder, memory_base, pointer_var);
+                for (offset, multiplier) in targets {
+                    let target_var =
+                        offset_pointer(&mut builder, pointer_var, *offset, wrap_len, &mut next_var_id);
+                    let existing = load_cell(&mut builder, memory_base, target_var);
+                    let factor = builder.ins().iconst(types::I8, *multiplier as i64);
+                    let scaled = builder.ins().imul(current, factor);
+                    let updated = builder.ins().iadd(existing, scaled);
+                    store_cell(&mut builder, memory_base, target_var, updated);
+                }
+                let zero = builder.ins().iconst(types::I8, 0);
+                store_cell(&mut builder, memory_base, pointer_var, zero);
+            }
+            Instructions::LoopStart(_) => {
+                let (body, after) = *loop_blocks[position].get_or_insert_with(|| {
+                    (builder.create_block(), builder.create_block())
+                });
+                let cell = load_cell(&mut builder, memory_base, pointer_var);
+                builder.ins().brif(cell, body, &[], after, &[]);
+                builder.switch_to_block(body);
+            }
+            Instructions::LoopEnd(start) => {
+                let (body, after) = loop_blocks[*start].expect("LoopStart precedes its LoopEnd");
+                let cell = load_cell(&mut builder, memory_base, pointer_var);
+                builder.ins().brif(cell, body, &[], after, &[]);
+                builder.seal_block(body);
+                builder.switch_to_block(after);
+                builder.seal_block(after);
+            }
+            Instructions::ScanRight | Instructions::ScanLeft => {
+                // Loop: while memory[pointer] != 0, step the pointer.
+                let step = builder.create_block();
+                let after = builder.create_block();
+                let cell = load_cell(&mut builder, memory_base, pointer_var);
+                builder.ins().brif(cell, step, &[], after, &[]);
+                builder.switch_to_block(step);
+                let pointer = builder.use_var(pointer_var);
+                let one = builder.ins().iconst(types::I64, 1);
+                let moved = if matches!(instruction, Instructions::ScanRight) {
+                    builder.ins().iadd(pointer, one)
+                } else {
+                    builder.ins().isub(pointer, one)
+                };
+                let moved = wrap_pointer(&mut builder, moved, wrap_len);
+                builder.def_var(pointer_var, moved);
+                let cell = load_cell(&mut builder, memory_base, pointer_var);
+                builder.ins().brif(cell, step, &[], after, &[]);
+                builder.seal_block(step);
+                builder.switch_to_block(after);
+                builder.seal_block(after);
+            }
+        }
+    }
+
+    builder.ins().return_(&[]);
+    builder.finalize();
+
+    module
+        .define_function(func_id, &mut context)
+        .expect("failed to define the compiled program");
+    module.clear_context(&mut context);
+    return func_id;
+}
+
+fn wrap_pointer(builder: &mut FunctionBuilder, pointer: Value, wrap_len: Option<i64>) -> Value {
+    return match wrap_len {
+        Some(len) => {
+            // A left move can take the pointer below 0, so take the signed
+            // remainder first and shift it into range before the final `urem`.
+            let len = builder.ins().iconst(types::I64, len);
+            let rem = builder.ins().srem(pointer, len);
+            let shifted = builder.ins().iadd(rem, len);
+            builder.ins().urem(shifted, len)
+        }
+        None => pointer,
+    };
+}
+
+// Pointer math in this crate treats `PointerLeft` as moving right and
+// `PointerRight` as moving left (see `compile`'s inversion), so a positive
+// `MoveMul` offset still means "toward higher addresses" — the same
+// convention `move_pointer` uses for the interpreter and threaded engines,
+// hence the `iadd` rather than `isub`.
+fn offset_pointer(
+    builder: &mut FunctionBuilder,
+    pointer_var: Variable,
+    offset: isize,
+    wrap_len: Option<i64>,
+    next_var_id: &mut u32,
+) -> Variable {
+    let pointer = builder.use_var(pointer_var);
+    let delta = builder.ins().iconst(types::I64, offset as i64);
+    let moved = builder.ins().iadd(pointer, delta);
+    let moved = wrap_pointer(builder, moved, wrap_len);
+    let scratch = Variable::new(*next_var_id as usize);
+    *next_var_id += 1;
+    builder.declare_var(scratch, types::I64);
+    builder.def_var(scratch, moved);
+    return scratch;
+}
+
+fn load_cell(builder: &mut FunctionBuilder, memory_base: Value, pointer_var: Variable) -> Value {
+    let pointer = builder.use_var(pointer_var);
+    let address = builder.ins().iadd(memory_base, pointer);
+    return builder.ins().load(types::I8, MemFlags::trusted(), address, 0);
+}
+
+fn store_cell(builder: &mut FunctionBuilder, memory_base: Value, pointer_var: Variable, value: Value) {
+    let pointer = builder.use_var(pointer_var);
+    let address = builder.ins().iadd(memory_base, pointer);
+    builder.ins().store(MemFlags::trusted(), value, address, 0);
+}
@@ -1,5 +1,152 @@
+use std::io::{BufRead, BufReader, Read};
 use std::str;
 
+mod jit;
+
+pub(crate) const TAPE_LEN: usize = 30_000;
+
+/// Controls what `GetChar` writes into the current cell once the input
+/// stream is exhausted, since BF dialects disagree on the convention.
+pub enum EofBehavior {
+    /// Leave the cell at whatever value it already held.
+    Unchanged,
+    /// Write a `0` byte into the cell.
+    Zero,
+    /// Write a `255` (`-1` as `u8`) byte into the cell.
+    NegOne,
+}
+
+/// How the tape's pointer behaves when it would move before cell 0 or past
+/// the configured `tape_len`.
+pub enum TapeBehavior {
+    /// A fixed-size tape; moving out of bounds panics, or returns
+    /// `Err(BfError::PointerOutOfBounds)` when `Config::checked` is set.
+    /// `Engine::Jit` does no bounds checks on this tape.
+    Bounded,
+    /// The pointer wraps around modulo `tape_len`.
+    Wrapping,
+    /// The tape grows on demand when the pointer moves right past the
+    /// current end. Moving left past cell 0 is still out of bounds.
+    Growing,
+}
+
+/// Configures the tape a program runs against, since BF dialects disagree
+/// on its size and on what happens at its edges.
+pub struct Config {
+    pub tape_len: usize,
+    pub tape_behavior: TapeBehavior,
+    /// When `true`, an out-of-bounds pointer move returns `Err` instead of
+    /// panicking.
+    pub checked: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        return Config {
+            tape_len: TAPE_LEN,
+            tape_behavior: TapeBehavior::Bounded,
+            checked: false,
+        };
+    }
+}
+
+/// Errors surfaced by a checked `Config` instead of panicking.
+#[derive(Debug)]
+pub enum BfError {
+    /// The pointer moved before cell 0, or past `tape_len` on a `Bounded`
+    /// tape.
+    PointerOutOfBounds,
+    /// The chosen `Engine` cannot honor this `Config`.
+    UnsupportedConfig,
+}
+
+/// Selects which backend runs a compiled `Program`. All engines share the
+/// same compiled `Vec<Instructions>`, so a program compiled once can be run
+/// on any of them.
+pub enum Engine {
+    /// The tree-walking interpreter: a straightforward `match` per op.
+    Interpreter,
+    /// Replaces the big `match` with function-pointer dispatch per op, cutting
+    /// per-instruction branch overhead at the cost of a compile step.
+    Threaded,
+    /// Lowers the instruction stream to native code via Cranelift.
+    Jit,
+    /// Picks an engine based on the size of the compiled program.
+    Auto,
+}
+
+/// A program compiled once via `compile_program`, ready to run on any
+/// `Engine` as many times as needed.
+pub struct Program {
+    instructions: Vec<Instructions>,
+    /// Whether the program contains a `,` and therefore reads `input`.
+    pub uses_input: bool,
+}
+
+/// Compiles `source` into a `Program` without running it, so callers can
+/// reuse the same compiled instructions across many `run` calls and engines.
+pub fn compile_program(source: &str) -> Program {
+    let instructions = compile(&optimize(&minify(source)));
+    let uses_input = instructions
+        .iter()
+        .any(|instruction| matches!(instruction, Instructions::GetChar));
+    return Program {
+        instructions,
+        uses_input,
+    };
+}
+
+impl Program {
+    /// Runs this program on the chosen `Engine` against the tape described
+    /// by `config`.
+    pub fn run(
+        &self,
+        engine: Engine,
+        print_live: bool,
+        input: impl Read,
+        eof_behavior: EofBehavior,
+        config: Config,
+    ) -> Result<String, BfError> {
+        let mut input = BufReader::new(input);
+        return match resolve_engine(engine, self.instructions.len(), &config) {
+            Engine::Jit => jit::run_jit(
+                &self.instructions,
+                print_live,
+                &mut input,
+                &eof_behavior,
+                &config,
+            ),
+            Engine::Threaded => execute_threaded(
+                &self.instructions,
+                print_live,
+                &mut input,
+                &eof_behavior,
+                &config,
+            ),
+            Engine::Interpreter | Engine::Auto => {
+                execute(&self.instructions, print_live, &mut input, &eof_behavior, &config)
+            }
+        };
+    }
+}
+
+// `Engine::Auto` picks the portable interpreter for small programs (where
+// the cost of building a dispatch table or JIT'ing outweighs the win),
+// the threaded interpreter for medium ones, and the JIT for large ones —
+// unless `config` isn't one the JIT supports (see `jit::run_jit`), in which
+// case it falls back to `Threaded` rather than surfacing a spurious
+// `BfError::UnsupportedConfig` for a config the other engines handle fine.
+fn resolve_engine(engine: Engine, program_len: usize, config: &Config) -> Engine {
+    let jit_compatible = !matches!(config.tape_behavior, TapeBehavior::Growing) && !config.checked;
+    match engine {
+        Engine::Auto if program_len > 10_000 && jit_compatible => Engine::Jit,
+        Engine::Auto if program_len > 500 => Engine::Threaded,
+        Engine::Auto => Engine::Interpreter,
+        other => other,
+    }
+}
+
+#[derive(Clone)]
 enum Instructions {
     PointerRight(usize),
     PointerLeft(usize),
@@ -17,6 +164,11 @@ enum Instructions {
 
     ScanLeft,
     ScanRight,
+
+    // Replaces a "multiply loop" like `[->+++<]`: zero the current cell,
+    // adding `memory[pointer_pos] * multiplier` (wrapping) to each
+    // `(offset, multiplier)` target first.
+    MoveMul(Vec<(isize, u8)>),
 }
 
 fn minify(source: &str) -> String {
@@ -143,38 +295,213 @@ fn compile(source: &str) -> Vec<Instructions> {
             _ => continue,
         }
     }
-    return program;
+    return fuse_multiply_loops(program);
+}
+
+// Detects the classic "multiply loop" idiom, e.g. `[->+<]` (copy) or
+// `[->+++<]` (multiply), and collapses the whole loop into a single
+// `MoveMul`, turning an O(cell value) loop into one pass over its targets.
+fn fuse_multiply_loops(program: Vec<Instructions>) -> Vec<Instructions> {
+    let mut fused = Vec::with_capacity(program.len());
+    let mut old_to_new = vec![0; program.len()];
+    let mut position = 0;
+    while position < program.len() {
+        if let Instructions::LoopStart(end) = program[position] {
+            if let Some(targets) = try_fuse_multiply_loop(&program, position, end) {
+                for skipped in position..=end {
+                    old_to_new[skipped] = fused.len();
+                }
+                fused.push(Instructions::MoveMul(targets));
+                position = end + 1;
+                continue;
+            }
+        }
+        old_to_new[position] = fused.len();
+        fused.push(program[position].clone());
+        position += 1;
+    }
+
+    // Loop targets were computed against the pre-fusion indices; remap them
+    // now that some loops have collapsed into a single instruction.
+    for instruction in fused.iter_mut() {
+        match instruction {
+            Instructions::LoopStart(target) => *target = old_to_new[*target],
+            Instructions::LoopEnd(target) => *target = old_to_new[*target],
+            _ => {}
+        }
+    }
+    return fused;
+}
+
+// A loop body qualifies for fusion when it nets zero pointer movement,
+// decrements the current cell by exactly 1 per iteration, and contains
+// nothing but pointer moves and add/sub (no I/O, no nested loops).
+fn try_fuse_multiply_loop(
+    program: &[Instructions],
+    start: usize,
+    end: usize,
+) -> Option<Vec<(isize, u8)>> {
+    let mut offset: isize = 0;
+    let mut deltas: Vec<(isize, i32)> = Vec::new();
+    for instruction in &program[start + 1..end] {
+        match instruction {
+            Instructions::PointerLeft(num) => offset += *num as isize,
+            Instructions::PointerRight(num) => offset -= *num as isize,
+            Instructions::Add(num) => accumulate_delta(&mut deltas, offset, i32::from(*num)),
+            Instructions::Sub(num) => accumulate_delta(&mut deltas, offset, -i32::from(*num)),
+            _ => return None,
+        }
+    }
+    if offset != 0 {
+        return None;
+    }
+
+    let current_delta = deltas
+        .iter()
+        .find(|(target_offset, _)| *target_offset == 0)
+        .map_or(0, |(_, delta)| *delta);
+    if current_delta.rem_euclid(256) != 255 {
+        return None;
+    }
+
+    return Some(
+        deltas
+            .into_iter()
+            .filter(|(target_offset, _)| *target_offset != 0)
+            .map(|(target_offset, delta)| (target_offset, delta.rem_euclid(256) as u8))
+            .collect(),
+    );
+}
+
+fn accumulate_delta(deltas: &mut Vec<(isize, i32)>, offset: isize, delta: i32) {
+    match deltas.iter_mut().find(|(existing, _)| *existing == offset) {
+        Some(entry) => entry.1 += delta,
+        None => deltas.push((offset, delta)),
+    }
+}
+
+// memchr-style SWAR scan: checks all 8 lanes of a u64 for a zero byte at
+// once, the same trick substring search uses to beat a byte-at-a-time loop.
+fn has_zero_byte(word: u64) -> bool {
+    const LO_BITS: u64 = 0x0101010101010101;
+    const HI_BITS: u64 = 0x8080808080808080;
+    return word.wrapping_sub(LO_BITS) & !word & HI_BITS != 0;
+}
+
+// Finds the first zero cell at or after `from`, scanning 8 bytes at a time
+// until a word containing a zero lane is found, then falling back to a
+// scalar scan to pinpoint the exact byte.
+fn find_zero_forward(memory: &[u8], from: usize) -> usize {
+    let mut index = from;
+    while index + 8 <= memory.len() {
+        let word = u64::from_ne_bytes(memory[index..index + 8].try_into().unwrap());
+        if has_zero_byte(word) {
+            break;
+        }
+        index += 8;
+    }
+    while memory[index] != 0 {
+        index += 1;
+    }
+    return index;
+}
+
+// Same as `find_zero_forward` but scanning backwards from `from`.
+fn find_zero_backward(memory: &[u8], from: usize) -> usize {
+    let mut index = from;
+    while index >= 7 {
+        let word = u64::from_ne_bytes(memory[index - 7..=index].try_into().unwrap());
+        if has_zero_byte(word) {
+            break;
+        }
+        index -= 8;
+    }
+    while memory[index] != 0 {
+        index -= 1;
+    }
+    return index;
+}
+
+// Resolves where a pointer move of `delta` cells lands according to
+// `config.tape_behavior`, growing `memory` on demand for `Growing` tapes.
+// Shared by every engine's `PointerLeft`/`PointerRight`/`MoveMul` handling
+// so the three dialects (bounded, wrapping, infinite-right-expanding) stay
+// in one place.
+fn move_pointer(
+    pointer_pos: usize,
+    delta: isize,
+    memory: &mut Vec<u8>,
+    config: &Config,
+) -> Result<usize, BfError> {
+    let moved = pointer_pos as isize + delta;
+    match config.tape_behavior {
+        TapeBehavior::Wrapping => {
+            return Ok(moved.rem_euclid(config.tape_len as isize) as usize);
+        }
+        TapeBehavior::Bounded => {
+            if moved < 0 || moved >= config.tape_len as isize {
+                if config.checked {
+                    return Err(BfError::PointerOutOfBounds);
+                }
+                panic!("pointer moved out of the bounded tape");
+            }
+            return Ok(moved as usize);
+        }
+        TapeBehavior::Growing => {
+            if moved < 0 {
+                if config.checked {
+                    return Err(BfError::PointerOutOfBounds);
+                }
+                panic!("pointer moved before cell 0");
+            }
+            let moved = moved as usize;
+            if moved >= memory.len() {
+                memory.resize(moved + 1, 0);
+            }
+            return Ok(moved);
+        }
+    }
 }
 
-fn execute(program: &Vec<Instructions>, print_live: bool) -> String {
-    let mut memory: [u8; 30_000] = [0; 30_000];
+fn execute(
+    program: &Vec<Instructions>,
+    print_live: bool,
+    input: &mut impl BufRead,
+    eof_behavior: &EofBehavior,
+    config: &Config,
+) -> Result<String, BfError> {
+    let mut memory: Vec<u8> = vec![0; config.tape_len];
     let mut program_pos: usize = 0;
     let mut pointer_pos: usize = 0;
     let mut output = Vec::new();
 
     while program_pos != program.len() {
-        match program[program_pos] {
-            Instructions::PointerLeft(num) => pointer_pos += num,
+        match &program[program_pos] {
+            Instructions::PointerLeft(num) => {
+                pointer_pos = move_pointer(pointer_pos, *num as isize, &mut memory, config)?;
+            }
 
-            Instructions::PointerRight(num) => pointer_pos -= num,
+            Instructions::PointerRight(num) => {
+                pointer_pos = move_pointer(pointer_pos, -(*num as isize), &mut memory, config)?;
+            }
 
             Instructions::Add(num) => {
-                memory[pointer_pos] = memory[pointer_pos].wrapping_add(num);
+                memory[pointer_pos] = memory[pointer_pos].wrapping_add(*num);
             }
 
             Instructions::Sub(num) => {
-                memory[pointer_pos] = memory[pointer_pos].wrapping_sub(num);
+                memory[pointer_pos] = memory[pointer_pos].wrapping_sub(*num);
             }
 
             Instructions::LoopStart(pos) => {
                 if memory[pointer_pos] == 0 {
-                    program_pos = pos;
+                    program_pos = *pos;
                 }
             }
 
             Instructions::LoopEnd(pos) => {
                 if memory[pointer_pos] != 0 {
-                    program_pos = pos;
+                    program_pos = *pos;
                 }
             }
 
@@ -186,7 +513,20 @@ fn execute(program: &Vec<Instructions>, print_live: bool) -> String {
             }
 
             Instructions::GetChar => {
-                panic!();
+                // Read through the BufReader's own buffer instead of a
+                // one-byte-at-a-time Read::read call, so large inputs
+                // stream without a syscall per cell.
+                match input.fill_buf() {
+                    Ok(buf) if !buf.is_empty() => {
+                        memory[pointer_pos] = buf[0];
+                        input.consume(1);
+                    }
+                    _ => match eof_behavior {
+                        EofBehavior::Unchanged => {}
+                        EofBehavior::Zero => memory[pointer_pos] = 0,
+                        EofBehavior::NegOne => memory[pointer_pos] = 255,
+                    },
+                }
             }
 
             Instructions::Clear => {
@@ -194,24 +534,209 @@ fn execute(program: &Vec<Instructions>, print_live: bool) -> String {
             }
 
             Instructions::ScanRight => {
-                while memory[pointer_pos] != 0 {
-                    pointer_pos += 1;
+                // Growing tapes need a zero cell ahead to terminate on;
+                // freshly grown bytes are zero-initialized, so this always
+                // finds one within the newly allocated block.
+                if matches!(config.tape_behavior, TapeBehavior::Growing)
+                    && pointer_pos + config.tape_len > memory.len()
+                {
+                    memory.resize(pointer_pos + config.tape_len, 0);
                 }
+                pointer_pos = find_zero_forward(&memory, pointer_pos);
             }
 
             Instructions::ScanLeft => {
-                while memory[pointer_pos] != 0 {
-                    pointer_pos -= 1;
+                pointer_pos = find_zero_backward(&memory, pointer_pos);
+            }
+
+            Instructions::MoveMul(targets) => {
+                let current = memory[pointer_pos];
+                if current != 0 {
+                    for (offset, multiplier) in targets {
+                        let target_pos = move_pointer(pointer_pos, *offset, &mut memory, config)?;
+                        memory[target_pos] =
+                            memory[target_pos].wrapping_add(current.wrapping_mul(*multiplier));
+                    }
                 }
+                memory[pointer_pos] = 0;
             }
         }
         program_pos += 1;
     }
-    return String::from_utf8(output).unwrap();
+    return Ok(String::from_utf8(output).unwrap());
+}
+
+// Threading state for `execute_threaded`: each op reads and mutates this in
+// place instead of capturing loose locals, since ops are built once up
+// front and run many times.
+struct ThreadedState<'a> {
+    memory: Vec<u8>,
+    pointer_pos: usize,
+    output: Vec<u8>,
+    input: &'a mut dyn BufRead,
+    eof_behavior: &'a EofBehavior,
+    print_live: bool,
+    config: &'a Config,
+}
+
+// Each op runs its instruction against the shared state and returns the
+// next program position, mirroring how `execute`'s `program_pos` is
+// advanced (a jump target always points at the loop's own bracket
+// instruction, with the usual `+ 1` folded into the returned position).
+type ThreadedOp = Box<dyn Fn(&mut ThreadedState) -> Result<usize, BfError>>;
+
+// Same semantics as `execute`, but replaces the per-iteration `match` with
+// a precompiled jump table of closures, one per instruction, cutting the
+// branch-prediction cost of dispatching through a big match every step.
+fn execute_threaded(
+    program: &[Instructions],
+    print_live: bool,
+    input: &mut impl BufRead,
+    eof_behavior: &EofBehavior,
+    config: &Config,
+) -> Result<String, BfError> {
+    let ops: Vec<ThreadedOp> = program
+        .iter()
+        .enumerate()
+        .map(|(position, instruction)| build_threaded_op(position, instruction))
+        .collect();
+
+    let mut state = ThreadedState {
+        memory: vec![0; config.tape_len],
+        pointer_pos: 0,
+        output: Vec::new(),
+        input,
+        eof_behavior,
+        print_live,
+        config,
+    };
+
+    let mut program_pos = 0;
+    while program_pos < ops.len() {
+        program_pos = ops[program_pos](&mut state)?;
+    }
+    return Ok(String::from_utf8(state.output).unwrap());
+}
+
+fn build_threaded_op(position: usize, instruction: &Instructions) -> ThreadedOp {
+    let next = position + 1;
+    match instruction {
+        Instructions::PointerLeft(num) => {
+            let num = *num as isize;
+            Box::new(move |state| {
+                state.pointer_pos = move_pointer(state.pointer_pos, num, &mut state.memory, state.config)?;
+                Ok(next)
+            })
+        }
+        Instructions::PointerRight(num) => {
+            let num = *num as isize;
+            Box::new(move |state| {
+                state.pointer_pos = move_pointer(state.pointer_pos, -num, &mut state.memory, state.config)?;
+                Ok(next)
+            })
+        }
+        Instructions::Add(num) => {
+            let num = *num;
+            Box::new(move |state| {
+                state.memory[state.pointer_pos] = state.memory[state.pointer_pos].wrapping_add(num);
+                Ok(next)
+            })
+        }
+        Instructions::Sub(num) => {
+            let num = *num;
+            Box::new(move |state| {
+                state.memory[state.pointer_pos] = state.memory[state.pointer_pos].wrapping_sub(num);
+                Ok(next)
+            })
+        }
+        Instructions::LoopStart(target) => {
+            let target = *target;
+            Box::new(move |state| {
+                if state.memory[state.pointer_pos] == 0 {
+                    Ok(target + 1)
+                } else {
+                    Ok(next)
+                }
+            })
+        }
+        Instructions::LoopEnd(target) => {
+            let target = *target;
+            Box::new(move |state| {
+                if state.memory[state.pointer_pos] != 0 {
+                    Ok(target + 1)
+                } else {
+                    Ok(next)
+                }
+            })
+        }
+        Instructions::PutChar => Box::new(move |state| {
+            let value = state.memory[state.pointer_pos];
+            state.output.push(value);
+            if state.print_live {
+                print!("{}", value as char);
+            }
+            Ok(next)
+        }),
+        Instructions::GetChar => Box::new(move |state| {
+            match state.input.fill_buf() {
+                Ok(buf) if !buf.is_empty() => {
+                    state.memory[state.pointer_pos] = buf[0];
+                    state.input.consume(1);
+                }
+                _ => match state.eof_behavior {
+                    EofBehavior::Unchanged => {}
+                    EofBehavior::Zero => state.memory[state.pointer_pos] = 0,
+                    EofBehavior::NegOne => state.memory[state.pointer_pos] = 255,
+                },
+            }
+            Ok(next)
+        }),
+        Instructions::Clear => Box::new(move |state| {
+            state.memory[state.pointer_pos] = 0;
+            Ok(next)
+        }),
+        Instructions::ScanRight => Box::new(move |state| {
+            if matches!(state.config.tape_behavior, TapeBehavior::Growing)
+                && state.pointer_pos + state.config.tape_len > state.memory.len()
+            {
+                let new_len = state.pointer_pos + state.config.tape_len;
+                state.memory.resize(new_len, 0);
+            }
+            state.pointer_pos = find_zero_forward(&state.memory, state.pointer_pos);
+            Ok(next)
+        }),
+        Instructions::ScanLeft => Box::new(move |state| {
+            state.pointer_pos = find_zero_backward(&state.memory, state.pointer_pos);
+            Ok(next)
+        }),
+        Instructions::MoveMul(targets) => {
+            let targets = targets.clone();
+            Box::new(move |state| {
+                let current = state.memory[state.pointer_pos];
+                if current != 0 {
+                    for (offset, multiplier) in &targets {
+                        let target_pos =
+                            move_pointer(state.pointer_pos, *offset, &mut state.memory, state.config)?;
+                        state.memory[target_pos] =
+                            state.memory[target_pos].wrapping_add(current.wrapping_mul(*multiplier));
+                    }
+                }
+                state.memory[state.pointer_pos] = 0;
+                Ok(next)
+            })
+        }
+    }
 }
 
-pub fn evaluate(source: &str, print_live: bool) -> String {
-    return execute(&compile(&optimize(&minify(&source))), print_live);
+pub fn evaluate(
+    source: &str,
+    print_live: bool,
+    input: impl Read,
+    eof_behavior: EofBehavior,
+    engine: Engine,
+    config: Config,
+) -> Result<String, BfError> {
+    return compile_program(source).run(engine, print_live, input, eof_behavior, config);
 }
 
 #[cfg(test)]
@@ -220,6 +745,186 @@ mod tests {
     
     #[test]
     fn hello_world() {
-        assert_eq!(evaluate("++++++++++[>+++++++>++++++++++>+++>+<<<<-]>++.>+.+++++++..+++.>++.<<+++++++++++++++.>.+++.------.--------.>+.>.", false), "Hello World!\n");
+        assert_eq!(evaluate("++++++++++[>+++++++>++++++++++>+++>+<<<<-]>++.>+.+++++++..+++.>++.<<+++++++++++++++.>.+++.------.--------.>+.>.", false, std::io::empty(), EofBehavior::Unchanged, Engine::Interpreter, Config::default()).unwrap(), "Hello World!\n");
+    }
+
+    #[test]
+    fn reads_input_with_getchar() {
+        assert_eq!(evaluate(",.", false, "A".as_bytes(), EofBehavior::Unchanged, Engine::Interpreter, Config::default()).unwrap(), "A");
+    }
+
+    #[test]
+    fn getchar_at_eof_writes_zero() {
+        assert_eq!(evaluate(",.", false, std::io::empty(), EofBehavior::Zero, Engine::Interpreter, Config::default()).unwrap(), "\0");
+    }
+
+    #[test]
+    fn fuses_copy_loop() {
+        // [->+<] copies cell 0 into cell 1, which should fuse into a single
+        // MoveMul instead of looping 5 times.
+        assert_eq!(
+            evaluate(
+                "+++++[->+<]>.",
+                false,
+                std::io::empty(),
+                EofBehavior::Unchanged,
+                Engine::Interpreter,
+                Config::default()
+            )
+            .unwrap(),
+            "\u{5}"
+        );
+    }
+
+    #[test]
+    fn fuses_multiply_loop_with_multiple_targets() {
+        // [->++>+++<<] multiplies cell 0 by 2 into cell 1 and by 3 into cell 2.
+        assert_eq!(
+            evaluate(
+                "+++[->++>+++<<]>.>.",
+                false,
+                std::io::empty(),
+                EofBehavior::Unchanged,
+                Engine::Interpreter,
+                Config::default()
+            )
+            .unwrap(),
+            "\u{6}\u{9}"
+        );
+    }
+
+    #[test]
+    fn scan_right_finds_zero_across_many_cells() {
+        // Set 20 consecutive cells to a nonzero value, then `[>]` should
+        // land the pointer on the first zero cell after them.
+        let source = "+".to_owned() + &">+".repeat(19) + "[>].";
+        assert_eq!(
+            evaluate(&source, false, std::io::empty(), EofBehavior::Unchanged, Engine::Interpreter, Config::default()).unwrap(),
+            "\0"
+        );
+    }
+
+    #[test]
+    fn scan_left_finds_zero_across_many_cells() {
+        // Mirror of the above, walking back to the left with `[<]`.
+        let source = ">".repeat(20) + "+" + &"<+".repeat(19) + "[<].";
+        assert_eq!(
+            evaluate(&source, false, std::io::empty(), EofBehavior::Unchanged, Engine::Interpreter, Config::default()).unwrap(),
+            "\0"
+        );
+    }
+
+    #[test]
+    fn threaded_engine_matches_interpreter() {
+        let source = "++++++++++[>+++++++>++++++++++>+++>+<<<<-]>++.>+.+++++++..+++.>++.<<+++++++++++++++.>.+++.------.--------.>+.>.";
+        let program = compile_program(source);
+        assert_eq!(
+            program
+                .run(Engine::Threaded, false, std::io::empty(), EofBehavior::Unchanged, Config::default())
+                .unwrap(),
+            "Hello World!\n"
+        );
+    }
+
+    #[test]
+    fn auto_engine_matches_interpreter() {
+        let source = "++++++++++[>+++++++>++++++++++>+++>+<<<<-]>++.>+.+++++++..+++.>++.<<+++++++++++++++.>.+++.------.--------.>+.>.";
+        assert_eq!(
+            evaluate(source, false, std::io::empty(), EofBehavior::Unchanged, Engine::Auto, Config::default()).unwrap(),
+            "Hello World!\n"
+        );
+    }
+
+    #[test]
+    fn auto_engine_falls_back_from_jit_for_incompatible_config() {
+        // Large enough (> 10_000 instructions) that Auto would pick Jit if
+        // the config allowed it; Growing isn't Jit-compatible, so Auto
+        // should fall back to Threaded instead of returning
+        // BfError::UnsupportedConfig for a config the other engines handle.
+        let source = "+>".repeat(6_000);
+        let config = Config {
+            tape_len: 1,
+            tape_behavior: TapeBehavior::Growing,
+            checked: false,
+        };
+        assert!(evaluate(&source, false, std::io::empty(), EofBehavior::Unchanged, Engine::Auto, config).is_ok());
+    }
+
+    #[test]
+    fn jit_engine_matches_interpreter() {
+        let source = "++++++++++[>+++++++>++++++++++>+++>+<<<<-]>++.>+.+++++++..+++.>++.<<+++++++++++++++.>.+++.------.--------.>+.>.";
+        let program = compile_program(source);
+        assert_eq!(
+            program
+                .run(Engine::Jit, false, std::io::empty(), EofBehavior::Unchanged, Config::default())
+                .unwrap(),
+            "Hello World!\n"
+        );
+    }
+
+    #[test]
+    fn jit_getchar_at_eof_leaves_nonzero_pointer_cell_unchanged() {
+        // Move onto cell 1 before `,`, so a bug that reads/writes cell 0
+        // instead of the actual current cell would show up here.
+        assert_eq!(
+            evaluate(
+                "+>,.",
+                false,
+                std::io::empty(),
+                EofBehavior::Unchanged,
+                Engine::Jit,
+                Config::default()
+            )
+            .unwrap(),
+            "\0"
+        );
+    }
+
+    #[test]
+    fn compile_program_reports_metadata() {
+        assert!(!compile_program("+.").uses_input);
+        assert!(compile_program(",.").uses_input);
+    }
+
+    #[test]
+    fn wrapping_tape_wraps_pointer() {
+        // On a 5-cell wrapping tape, moving 5 cells right lands back on cell 0.
+        let config = Config {
+            tape_len: 5,
+            tape_behavior: TapeBehavior::Wrapping,
+            checked: false,
+        };
+        let source = "+".to_owned() + &">".repeat(5) + ".";
+        assert_eq!(
+            evaluate(&source, false, std::io::empty(), EofBehavior::Unchanged, Engine::Interpreter, config).unwrap(),
+            "\u{1}"
+        );
+    }
+
+    #[test]
+    fn growing_tape_extends_on_demand() {
+        // A 1-cell growing tape should still be able to move far to the
+        // right, since it resizes instead of rejecting the move.
+        let config = Config {
+            tape_len: 1,
+            tape_behavior: TapeBehavior::Growing,
+            checked: false,
+        };
+        let source = ">".repeat(50) + "+.";
+        assert_eq!(
+            evaluate(&source, false, std::io::empty(), EofBehavior::Unchanged, Engine::Interpreter, config).unwrap(),
+            "\u{1}"
+        );
+    }
+
+    #[test]
+    fn checked_bounded_tape_reports_out_of_bounds_instead_of_panicking() {
+        let config = Config {
+            tape_len: 1,
+            tape_behavior: TapeBehavior::Bounded,
+            checked: true,
+        };
+        let result = evaluate(">", false, std::io::empty(), EofBehavior::Unchanged, Engine::Interpreter, config);
+        assert!(matches!(result, Err(BfError::PointerOutOfBounds)));
     }
 }